@@ -44,22 +44,119 @@ where
     fn init_with_size(size: N, f: Func) -> Self;
 }
 
+/// Initialize an object with a static size from a fallible function,
+/// short-circuiting on the first `Err`
+///
+/// Example:
+// the array impl only exists behind the `unsafe` feature, so this doctest
+// only compiles when that feature is enabled (same caveat as `InitWithIndex`)
+#[cfg_attr(feature = "unsafe", doc = "```rust")]
+#[cfg_attr(not(feature = "unsafe"), doc = "```rust,ignore")]
+/// # use initialize::*;
+/// ##[derive(PartialEq, Eq, Debug)]
+/// struct MyStruct {
+///     some_data: usize
+/// }
+///
+/// let result: Result<[MyStruct; 2], &str> = TryInitWithIndex::try_init_with(|index| {
+///     if index == 1 { Err("bad index") } else { Ok(MyStruct {some_data: index * 3}) }
+/// });
+///# assert_eq!(result, Err("bad index"));
+/// ```
+pub trait TryInitWithIndex<N, T, E, Func: Fn(N) -> Result<T, E>>
+where
+    Self: Index<N> + Sized,
+{
+    fn try_init_with(f: Func) -> Result<Self, E>;
+}
+
+/// Initialize an object with a dynamic size from a fallible function,
+/// short-circuiting on the first `Err`
+///
+/// Example
+/// ``` rust
+/// # use initialize::*;
+/// ##[derive(PartialEq, Eq, Debug)]
+/// struct MyStruct {
+///     some_data: usize
+/// }
+/// let result = Vec::<MyStruct>::try_init_with_size(20, |index| {
+///     if index == 5 { Err("bad index") } else { Ok(MyStruct {some_data: index * 3}) }
+/// });
+///# assert_eq!(result, Err("bad index"));
+///```
+pub trait TryInitWithDynamicIndex<N, T, E, Func: Fn(N) -> Result<T, E>>
+where
+    Self: Index<N> + Sized,
+{
+    fn try_init_with_size(size: N, f: Func) -> Result<Self, E>;
+}
+
+/// Drops the first `initialized` elements of the array backing `dst` when
+/// dropped.
+///
+/// Used by the `unsafe` array impls to unwind cleanly if the user's closure
+/// panics partway through construction: without it, already-written
+/// elements would never have their destructors run (a leak, and a
+/// potential double-free if the caller retries into the same memory).
+#[cfg(feature = "unsafe")]
+struct ArrayGuard<T> {
+    dst: *mut T,
+    initialized: usize,
+}
+
+#[cfg(feature = "unsafe")]
+impl<T> Drop for ArrayGuard<T> {
+    fn drop(&mut self) {
+        unsafe {
+            std::ptr::drop_in_place(std::ptr::slice_from_raw_parts_mut(
+                self.dst,
+                self.initialized,
+            ));
+        }
+        /*
+        Safety:
+        `dst` points at the base of the array and `initialized` only ever
+        counts elements that were actually written by the init loop, so this
+        drops exactly the elements that exist and nothing past them.
+        */
+    }
+}
+
 #[cfg(feature = "unsafe")]
 impl<const SIZE: usize, T, Func: Fn(usize) -> T> InitWithIndex<usize, T, Func> for [T; SIZE] {
     fn init_with(f: Func) -> Self {
-        let mut arr: std::mem::MaybeUninit<[T; SIZE]> = std::mem::MaybeUninit::zeroed();
+        // No element is ever observed in a zeroed-but-invalid state: slots
+        // stay uninitialized (not zero-filled) until `f` writes them, so we
+        // skip the `SIZE * size_of::<T>()` of zeroing that `MaybeUninit::zeroed`
+        // would otherwise do and immediately discard.
+        let mut arr: [std::mem::MaybeUninit<T>; SIZE] =
+            [const { std::mem::MaybeUninit::uninit() }; SIZE];
 
-        let ptr = arr.as_mut_ptr();
+        let ptr = arr.as_mut_ptr() as *mut T;
+        let mut guard = ArrayGuard::<T> {
+            dst: ptr,
+            initialized: 0,
+        };
         for index in 0..SIZE {
-            unsafe { std::ptr::write((ptr as *mut T).add(index), f(index)) }
+            unsafe { std::ptr::write(ptr.add(index), f(index)) }
             /*
             Safety:
-            We just created the zeroed out the chunk of memory with MaybeUninit, so it is safe to write to
-            We are aligning by the size of t (add does it automatically) so the ptr is aligned correctly
+            Each slot is uninitialized memory sized and aligned for `T` (it came
+            straight from `MaybeUninit::uninit`), so writing to it is safe, and
+            `add` advances by `size_of::<T>()` so the pointer stays aligned
             */
+            guard.initialized += 1; // only advance after a successful write, so a panic in `f` leaves `guard` pointing at exactly what's been constructed
         }
+        std::mem::forget(guard); // every element is now initialized, so the array's own Drop takes over and the guard must not double-drop them
 
-        unsafe { arr.assume_init() } // We just initialized every value in the array
+        unsafe { std::mem::transmute_copy(&arr) }
+        // Safety:
+        // every slot in `arr` was written by the loop above, so reinterpreting
+        // `[MaybeUninit<T>; SIZE]` as `[T; SIZE]` reads back fully initialized
+        // values; `transmute_copy` (rather than `transmute`) is needed because
+        // the compiler can't prove the two generic-length arrays are the same
+        // size on its own
     }
 }
 
@@ -73,6 +170,150 @@ impl<T, Func: Fn(usize) -> T> InitWithDynamicIndex<usize, T, Func> for Vec<T> {
     }
 }
 
+#[cfg(feature = "unsafe")]
+impl<const SIZE: usize, T, E, Func: Fn(usize) -> Result<T, E>> TryInitWithIndex<usize, T, E, Func>
+    for [T; SIZE]
+{
+    fn try_init_with(f: Func) -> Result<Self, E> {
+        let mut arr: [std::mem::MaybeUninit<T>; SIZE] =
+            [const { std::mem::MaybeUninit::uninit() }; SIZE];
+
+        let ptr = arr.as_mut_ptr() as *mut T;
+        let mut guard = ArrayGuard::<T> {
+            dst: ptr,
+            initialized: 0,
+        };
+        for index in 0..SIZE {
+            let value = f(index)?; // guard drops elements 0..index here on the way out, so an `Err` leaks nothing
+            unsafe { std::ptr::write(ptr.add(index), value) }
+            /*
+            Safety:
+            Same as `InitWithIndex::init_with`: each slot is uninitialized
+            memory sized and aligned for `T`, so writing to it is safe
+            */
+            guard.initialized += 1;
+        }
+        std::mem::forget(guard); // every element is now initialized, so the array's own Drop takes over and the guard must not double-drop them
+
+        Ok(unsafe { std::mem::transmute_copy(&arr) })
+        // Safety: every slot in `arr` was written by the loop above, same as `init_with`
+    }
+}
+
+impl<T, E, Func: Fn(usize) -> Result<T, E>> TryInitWithDynamicIndex<usize, T, E, Func> for Vec<T> {
+    fn try_init_with_size(size: usize, f: Func) -> Result<Self, E> {
+        let mut vec = Vec::with_capacity(size);
+        for index in 0..size {
+            vec.push(f(index)?); // `vec`'s own elements are dropped as usual when it's dropped on the way out
+        }
+        Ok(vec)
+    }
+}
+
+/// Returns an array of `SIZE` uninitialized slots to build up manually.
+///
+/// Unlike `init_with`, this doesn't force a fixed `0..SIZE` ascending
+/// construction order: callers can write slots out of order, or interleave
+/// writes with other work (e.g. reading into the array from I/O), then hand
+/// the fully-written array to [`assume_all_init`].
+#[cfg(feature = "unsafe")]
+pub fn uninit_array<T, const SIZE: usize>() -> [std::mem::MaybeUninit<T>; SIZE] {
+    [const { std::mem::MaybeUninit::uninit() }; SIZE]
+}
+
+/// Converts a fully-written `[MaybeUninit<T>; SIZE]` into `[T; SIZE]`.
+///
+/// # Safety
+/// Every slot in `arr` must have been written before calling this; any slot
+/// that's still uninitialized becomes an instance of `T` containing
+/// arbitrary bytes, which is immediate undefined behavior for most `T`.
+#[cfg(feature = "unsafe")]
+pub unsafe fn assume_all_init<T, const SIZE: usize>(
+    arr: [std::mem::MaybeUninit<T>; SIZE],
+) -> [T; SIZE] {
+    unsafe { std::mem::transmute_copy(&arr) }
+    // Safety: forwarded to the caller of this `unsafe fn`, who must guarantee
+    // every slot was written; `transmute_copy` (rather than `transmute`) is
+    // needed because the compiler can't prove the two generic-length arrays
+    // are the same size on its own
+}
+
+/// A fixed-size 2D grid, indexable by `(row, col)`.
+///
+/// `InitWithIndex`/`InitWithDynamicIndex` are defined in terms of `Index<N>`,
+/// but nested arrays and `Vec<Vec<T>>` only implement `Index<usize>` (one
+/// dimension at a time) — neither this crate nor `std::ops::Index` owns
+/// enough of `[[T; C]; R]` for us to add `Index<(usize, usize)>` to it
+/// directly, so coordinate-indexed init needs its own local type to hang
+/// that impl on. This is a deliberate deviation from a plain `[[T; C]; R]`:
+/// `.0` and the `From` impl below get you back to the nested array once it's
+/// built, at the cost of a one-field wrapper during construction.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Grid<T, const ROWS: usize, const COLS: usize>(pub [[T; COLS]; ROWS]);
+
+impl<T, const ROWS: usize, const COLS: usize> Index<(usize, usize)> for Grid<T, ROWS, COLS> {
+    type Output = T;
+
+    fn index(&self, (row, col): (usize, usize)) -> &T {
+        &self.0[row][col]
+    }
+}
+
+impl<T, const ROWS: usize, const COLS: usize> From<Grid<T, ROWS, COLS>> for [[T; COLS]; ROWS] {
+    fn from(grid: Grid<T, ROWS, COLS>) -> Self {
+        grid.0
+    }
+}
+
+#[cfg(feature = "unsafe")]
+impl<const ROWS: usize, const COLS: usize, T, Func: Fn((usize, usize)) -> T>
+    InitWithIndex<(usize, usize), T, Func> for Grid<T, ROWS, COLS>
+{
+    fn init_with(f: Func) -> Self {
+        // Reuses the existing flat, panic-safe per-row/per-column
+        // construction: the outer `init_with` builds each row, and each row
+        // is itself built one cell at a time via the same array impl.
+        Grid(InitWithIndex::init_with(|row: usize| {
+            InitWithIndex::init_with(|col: usize| f((row, col)))
+        }))
+    }
+}
+
+/// A dynamically-sized 2D grid, indexable by `(row, col)`.
+///
+/// The dynamic counterpart to [`Grid`], for the same `Index<(usize, usize)>`
+/// orphan-rule reason: `Vec<Vec<T>>` can't have that impl added to it
+/// directly from this crate. `.0`/`From` unwrap back to a plain
+/// `Vec<Vec<T>>` the same way.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct GridVec<T>(pub Vec<Vec<T>>);
+
+impl<T> Index<(usize, usize)> for GridVec<T> {
+    type Output = T;
+
+    fn index(&self, (row, col): (usize, usize)) -> &T {
+        &self.0[row][col]
+    }
+}
+
+impl<T> From<GridVec<T>> for Vec<Vec<T>> {
+    fn from(grid: GridVec<T>) -> Self {
+        grid.0
+    }
+}
+
+impl<T, Func: Fn((usize, usize)) -> T> InitWithDynamicIndex<(usize, usize), T, Func>
+    for GridVec<T>
+{
+    fn init_with_size((rows, cols): (usize, usize), f: Func) -> Self {
+        // Same composition as `Grid::init_with`, but over the dynamic `Vec`
+        // impl instead of the fixed-size array impl.
+        GridVec(InitWithDynamicIndex::init_with_size(rows, |row: usize| {
+            InitWithDynamicIndex::init_with_size(cols, |col: usize| f((row, col)))
+        }))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -96,6 +337,38 @@ mod tests {
             ]
         )
     }
+
+    #[cfg(feature = "unsafe")]
+    #[test]
+    fn array_init_with_panic_drops_only_constructed_elements() {
+        use std::panic::AssertUnwindSafe;
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        struct DropCounter<'a>(&'a AtomicUsize);
+
+        impl Drop for DropCounter<'_> {
+            fn drop(&mut self) {
+                self.0.fetch_add(1, Ordering::SeqCst);
+            }
+        }
+
+        let drops = AtomicUsize::new(0);
+        let result = std::panic::catch_unwind(AssertUnwindSafe(|| {
+            let _: [DropCounter; 5] = InitWithIndex::init_with(|index| {
+                if index == 3 {
+                    panic!("boom");
+                }
+                DropCounter(&drops)
+            });
+        }));
+
+        assert!(result.is_err());
+        // indices 0, 1, 2 were constructed and must each be dropped exactly
+        // once by the guard; index 3 panicked before a value existed and
+        // index 4 was never reached, so neither contributes a drop
+        assert_eq!(drops.load(Ordering::SeqCst), 3);
+    }
+
     #[test]
     fn vec_initialize_with_dynamic_index() {
         #[derive(PartialEq, Eq, Debug)]
@@ -107,4 +380,156 @@ mod tests {
 
         assert_eq!(array[99], MyData { _data: 990 })
     }
+
+    #[cfg(feature = "unsafe")]
+    #[test]
+    fn array_try_initialize_with_index() {
+        #[derive(PartialEq, Eq, Debug)]
+        struct MyData {
+            _data: usize,
+        }
+
+        let array: Result<[MyData; 3], &str> = TryInitWithIndex::try_init_with(|index| {
+            if index == 2 {
+                Err("bad index")
+            } else {
+                Ok(MyData { _data: index * 2 })
+            }
+        });
+
+        assert_eq!(array, Err("bad index"))
+    }
+
+    #[cfg(feature = "unsafe")]
+    #[test]
+    fn array_try_init_with_err_drops_only_constructed_elements() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        struct DropCounter<'a>(&'a AtomicUsize);
+
+        impl Drop for DropCounter<'_> {
+            fn drop(&mut self) {
+                self.0.fetch_add(1, Ordering::SeqCst);
+            }
+        }
+
+        let drops = AtomicUsize::new(0);
+        let result: Result<[DropCounter; 5], &str> = TryInitWithIndex::try_init_with(|index| {
+            if index == 3 {
+                Err("bad index")
+            } else {
+                Ok(DropCounter(&drops))
+            }
+        });
+
+        assert!(result.is_err());
+        // indices 0, 1, 2 were constructed and must each be dropped exactly
+        // once by the guard; index 3 returned `Err` before a value existed
+        // and index 4 was never reached, so neither contributes a drop
+        assert_eq!(drops.load(Ordering::SeqCst), 3);
+    }
+
+    #[test]
+    fn vec_try_initialize_with_dynamic_index() {
+        #[derive(PartialEq, Eq, Debug)]
+        struct MyData {
+            _data: usize,
+        }
+
+        let vec = Vec::<MyData>::try_init_with_size(100, |index| {
+            if index == 50 {
+                Err("bad index")
+            } else {
+                Ok(MyData { _data: index * 10 })
+            }
+        });
+
+        assert_eq!(vec, Err("bad index"))
+    }
+
+    #[cfg(feature = "unsafe")]
+    #[test]
+    fn array_manual_uninit_init() {
+        #[derive(PartialEq, Eq, Debug)]
+        struct MyData {
+            _data: usize,
+        }
+
+        let mut arr = uninit_array::<MyData, 3>();
+        for index in (0..3).rev() {
+            arr[index].write(MyData { _data: index * 2 });
+        }
+        let array = unsafe { assume_all_init(arr) };
+
+        assert_eq!(
+            array,
+            [
+                MyData { _data: 0 },
+                MyData { _data: 2 },
+                MyData { _data: 4 }
+            ]
+        )
+    }
+
+    #[cfg(feature = "unsafe")]
+    #[test]
+    fn grid_initialize_with_coordinates() {
+        #[derive(PartialEq, Eq, Debug)]
+        struct MyData {
+            _data: usize,
+        }
+
+        let grid: Grid<MyData, 2, 3> =
+            InitWithIndex::init_with(|(row, col)| MyData { _data: row * 3 + col });
+
+        assert_eq!(grid[(1, 2)], MyData { _data: 5 })
+    }
+
+    #[test]
+    fn grid_vec_initialize_with_dynamic_coordinates() {
+        #[derive(PartialEq, Eq, Debug)]
+        struct MyData {
+            _data: usize,
+        }
+
+        let grid: GridVec<MyData> =
+            InitWithDynamicIndex::init_with_size((4, 5), |(row, col)| MyData {
+                _data: row * 5 + col,
+            });
+
+        assert_eq!(grid[(3, 4)], MyData { _data: 19 })
+    }
+
+    #[cfg(feature = "unsafe")]
+    #[test]
+    fn grid_into_nested_array() {
+        #[derive(PartialEq, Eq, Debug)]
+        struct MyData {
+            _data: usize,
+        }
+
+        let grid: Grid<MyData, 2, 3> =
+            InitWithIndex::init_with(|(row, col)| MyData { _data: row * 3 + col });
+
+        let array: [[MyData; 3]; 2] = grid.into();
+
+        assert_eq!(array[1][2], MyData { _data: 5 })
+    }
+
+    #[test]
+    fn grid_vec_into_nested_vec() {
+        #[derive(PartialEq, Eq, Debug)]
+        struct MyData {
+            _data: usize,
+        }
+
+        let grid: GridVec<MyData> =
+            InitWithDynamicIndex::init_with_size((4, 5), |(row, col)| MyData {
+                _data: row * 5 + col,
+            });
+
+        let vec: Vec<Vec<MyData>> = grid.into();
+
+        assert_eq!(vec[3][4], MyData { _data: 19 })
+    }
 }